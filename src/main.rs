@@ -16,31 +16,71 @@ enum ArgState {
     Zoom,
     FrameCount,
     ThreadCount,
+    ColorMode,
+    Julia,
+    EndX,
+    EndY,
+    EndRange,
 }
 
 #[derive(Copy, Clone)]
 enum Algorithm {
     Mandelbrot,
+    Julia { cx: f64, cy: f64 },
+}
+
+#[derive(Copy, Clone)]
+enum ColorMode {
+    Classic,
+    Smooth,
+    Distance,
+}
+
+// A focus point and range, either the keyframe being rendered from or the
+// keyframe a video pans/zooms toward. Bundled together because they always
+// travel as a unit through frame_geometry and the arg parser.
+#[derive(Copy, Clone)]
+struct Keyframe {
+    x: f64,
+    y: f64,
+    range: f64,
+}
+
+// Parsed command line configuration, returned as a single struct rather than
+// a tuple since it has grown too many fields to destructure positionally
+// without the call site becoming unreadable (and order-dependent).
+struct Args {
+    focus: Keyframe,
+    end: Option<Keyframe>,
+    width: u32,
+    height: u32,
+    algorithm: Algorithm,
+    color_mode: ColorMode,
+    zoom: f64,
+    frames: u32,
+    threads: u32,
 }
 
 fn main() {
-    let (focus_x, focus_y, mut range, width, height, algorithm, zoom, frames, threads) = parse_args();
+    let args = parse_args();
 
-    let full_multiprocess_loops = frames/threads;
-    let remainder = frames - (full_multiprocess_loops * threads);
+    let full_multiprocess_loops = args.frames/args.threads;
+    let remainder = args.frames - (full_multiprocess_loops * args.threads);
+    let mut frame_idx = 0;
 
     for _i in 0..full_multiprocess_loops {
         let mut channels = Vec::new();
-        for _y in 0..threads {
+        for _y in 0..args.threads {
             let (tx, rx) = mpsc::channel();
             channels.push(rx);
 
-            let new_alg = algorithm.clone();
+            let (x, y, range, alg) = frame_geometry(args.focus, args.end, args.zoom, args.algorithm, frame_idx, args.frames);
+            let (width, height, color_mode) = (args.width, args.height, args.color_mode);
             thread::spawn(move || {
-                let buffer = gen_image(focus_x, focus_y, range, width, height, new_alg);
+                let buffer = gen_image(x, y, range, width, height, alg, color_mode);
                 tx.send(buffer).unwrap();
             });
-            range *= zoom;
+            frame_idx += 1;
         }
 
         // Gather images generated from other threads
@@ -56,12 +96,13 @@ fn main() {
         let (tx, rx) = mpsc::channel();
         channels.push(rx);
 
-        let new_alg = algorithm.clone();
+        let (x, y, range, alg) = frame_geometry(args.focus, args.end, args.zoom, args.algorithm, frame_idx, args.frames);
+        let (width, height, color_mode) = (args.width, args.height, args.color_mode);
         thread::spawn(move || {
-            let buffer = gen_image(focus_x, focus_y, range, width, height, new_alg);
+            let buffer = gen_image(x, y, range, width, height, alg, color_mode);
             tx.send(buffer).unwrap();
         });
-        range *= zoom;
+        frame_idx += 1;
     }
 
     // Gather images generated from other threads
@@ -72,7 +113,60 @@ fn main() {
 
 }
 
-fn parse_args() -> (f64, f64, f64, u32, u32, Algorithm, f64, u32, u32) {
+// Computes the fractal geometry to render for frame `frame_idx` of `frames`:
+// the (x, y, range) to pass to gen_image plus the per-frame Algorithm.  Each
+// frame's geometry is computed fresh from the original parameters rather
+// than accumulated by mutating shared state, so frames can be handed off to
+// worker threads independently of spawn order.
+//
+// When any of end_x/end_y/end_range is supplied, the video pans from the
+// focus keyframe to the end keyframe: center interpolates linearly and
+// range interpolates geometrically so the apparent zoom speed stays
+// constant. Otherwise Mandelbrot falls back to the original fixed-focus
+// zoom, and Julia keeps its starting range. In both cases a Julia set
+// sweeps its constant `c` around a circle (see frame_algorithm) -- which
+// animates far better than zooming toward a fixed point, since every frame
+// is already the whole connected set.
+fn frame_geometry(
+    focus: Keyframe, end: Option<Keyframe>, zoom: f64, algorithm: Algorithm, frame_idx: u32, frames: u32,
+) -> (f64, f64, f64, Algorithm) {
+    let alg = frame_algorithm(algorithm, frame_idx, frames);
+
+    if let Some(end) = end {
+        let progress = if frames <= 1 { 0f64 } else { (frame_idx as f64) / ((frames - 1) as f64) };
+        let x = focus.x + (end.x - focus.x) * progress;
+        let y = focus.y + (end.y - focus.y) * progress;
+        let range = focus.range * (end.range / focus.range).powf(progress);
+        (x, y, range, alg)
+    } else {
+        let range = match alg {
+            Algorithm::Mandelbrot => focus.range * zoom.powi(frame_idx as i32),
+            Algorithm::Julia { .. } => focus.range,
+        };
+        (focus.x, focus.y, range, alg)
+    }
+}
+
+// Computes the Algorithm to render for frame `frame_idx` of `frames`.
+// Mandelbrot is unaffected; a Julia set sweeps its constant `c` around a
+// circle across the video (see frame_geometry).
+fn frame_algorithm(algorithm: Algorithm, frame_idx: u32, frames: u32) -> Algorithm {
+    match algorithm {
+        Algorithm::Mandelbrot => algorithm,
+        Algorithm::Julia { cx, cy } => {
+            if frames <= 1 {
+                algorithm
+            } else {
+                let radius = (cx.powi(2) + cy.powi(2)).sqrt();
+                let start_angle = cy.atan2(cx);
+                let angle = start_angle + std::f64::consts::TAU * (frame_idx as f64) / (frames as f64);
+                Algorithm::Julia { cx: radius * angle.cos(), cy: radius * angle.sin() }
+            }
+        },
+    }
+}
+
+fn parse_args() -> Args {
     let mut state = ArgState::Initial;
     let mut focus_x = -1f64;
     let mut focus_y = 0f64;
@@ -80,7 +174,11 @@ fn parse_args() -> (f64, f64, f64, u32, u32, Algorithm, f64, u32, u32) {
     let mut width = 1920;
     let mut height = 1080;
     let mut algorithm = Algorithm::Mandelbrot;
+    let mut color_mode = ColorMode::Classic;
     let mut zoom = 0.99;
+    let mut end_x = None;
+    let mut end_y = None;
+    let mut end_range = None;
     let mut frames = 1;
     let mut threads = 1;
     for arg in env::args().skip(1) {
@@ -91,7 +189,12 @@ fn parse_args() -> (f64, f64, f64, u32, u32, Algorithm, f64, u32, u32) {
             "-w" | "--width" => state = ArgState::ImageWidth,
             "-ht" | "--height" => state = ArgState::ImageHeight,
             "-a" | "--algorithm" => state = ArgState::Algorithm,
+            "-j" | "--julia" => state = ArgState::Julia,
+            "-c" | "--color_mode" => state = ArgState::ColorMode,
             "-z" | "--zoom" => state = ArgState::Zoom,
+            "-X2" | "--end_x" => state = ArgState::EndX,
+            "-Y2" | "--end_y" => state = ArgState::EndY,
+            "-r2" | "--end_range" => state = ArgState::EndRange,
             "-f" | "--frames" => state = ArgState::FrameCount,
             "-t" | "--threads" => state = ArgState::ThreadCount,
             "-h" | "--help" => help_args(),
@@ -110,18 +213,62 @@ fn parse_args() -> (f64, f64, f64, u32, u32, Algorithm, f64, u32, u32) {
                         },
                     }
                 },
+                ArgState::Julia => {
+                    let parts: Vec<&str> = arg.split(',').collect();
+                    if parts.len() != 2 {
+                        eprintln!("Error {} must be formatted as CX,CY!", arg);
+                        help_args();
+                    }
+                    let cx: f64 = parts[0].parse().expect(format!("{} must be a floating point value!", parts[0]).as_str());
+                    let cy: f64 = parts[1].parse().expect(format!("{} must be a floating point value!", parts[1]).as_str());
+                    algorithm = Algorithm::Julia { cx, cy };
+                },
+                ArgState::ColorMode => {
+                    match arg.as_str() {
+                        "classic" | "Classic" => color_mode = ColorMode::Classic,
+                        "smooth" | "Smooth" => color_mode = ColorMode::Smooth,
+                        "distance" | "Distance" => color_mode = ColorMode::Distance,
+                        _ => {
+                            eprintln!("Error unknown color mode {}!", arg.as_str());
+                            help_args();
+                        },
+                    }
+                },
                 ArgState::Zoom => zoom = arg.parse().expect(format!("{} must be a floating point value!", arg).as_str()),
+                ArgState::EndX => end_x = Some(arg.parse().expect(format!("{} must be a floating point value!", arg).as_str())),
+                ArgState::EndY => end_y = Some(arg.parse().expect(format!("{} must be a floating point value!", arg).as_str())),
+                ArgState::EndRange => end_range = Some(arg.parse().expect(format!("{} must be a floating point value!", arg).as_str())),
                 ArgState::FrameCount => frames = arg.parse().expect(format!("{} must be a positive integral value!", arg).as_str()),
                 ArgState::ThreadCount => threads = arg.parse().expect(format!("{} must be a positive integral value!", arg).as_str()),
                 ArgState::Initial => {
-                    eprintln!("Error unknown argument {}! Expected either flags -x, -y, -r, -w, -h, -a, -z, -f, or -h", arg);
+                    eprintln!("Error unknown argument {}! Expected either flags -x, -y, -r, -w, -h, -a, -j, -c, -z, -X2, -Y2, -r2, -f, or -h", arg);
                     help_args();
                 },
             },
         }
     }
 
-    (focus_x, focus_y, range, width, height, algorithm, zoom, frames, threads)
+    let end = if end_x.is_some() || end_y.is_some() || end_range.is_some() {
+        Some(Keyframe {
+            x: end_x.unwrap_or(focus_x),
+            y: end_y.unwrap_or(focus_y),
+            range: end_range.unwrap_or(range),
+        })
+    } else {
+        None
+    };
+
+    Args {
+        focus: Keyframe { x: focus_x, y: focus_y, range },
+        end,
+        width,
+        height,
+        algorithm,
+        color_mode,
+        zoom,
+        frames,
+        threads,
+    }
 }
 
 fn help_args() {
@@ -142,8 +289,21 @@ fn help_args() {
     println!("-a | --algorithm      : Specify which algorithm to use for fractal generation");
     println!("                        Available algorithms: mandelbrot");
     println!("                        Default: mandelbrot");
+    println!("-j | --julia          : Render a Julia set with fixed constant CX,CY instead of Mandelbrot");
+    println!("                        Format: -j CX,CY");
+    println!("                        If -f > 1, the video sweeps CX,CY around a circle instead of applying -z");
+    println!("-c | --color_mode     : Specify how escape times are mapped to color");
+    println!("                        Available color modes: classic, smooth, distance");
+    println!("                        smooth uses a normalized iteration count to eliminate banding");
+    println!("                        distance estimates boundary distance for crisp edges when zoomed in");
+    println!("                        Default: classic");
     println!("-z | --zoom           : If frames > 2, this is the zoom amount between frames");
     println!("                        Default: 0.99");
+    println!("-X2 | --end_x         : X coordinate of the focus point of the last frame of a video");
+    println!("                        If any of -X2, -Y2, -r2 is given, the video pans and zooms from");
+    println!("                        the -x/-y/-r keyframe to the -X2/-Y2/-r2 keyframe instead of using -z");
+    println!("-Y2 | --end_y         : Y coordinate of the focus point of the last frame of a video");
+    println!("-r2 | --end_range     : Range of the last frame of a video");
     println!("-f | --frames         : Number of images to take, zooming in to the focal point for each image");
     println!("                        Set -f to 1 for a single image, or >= 2 for a video");
     println!("                        Default: 1");
@@ -158,27 +318,41 @@ fn help_args() {
     std::process::exit(0);
 }
 
-fn gen_image(focus_x: f64, focus_y: f64, range: f64, width: u32, height: u32, algorithm: Algorithm) -> Vec::<u8> {
+fn gen_image(focus_x: f64, focus_y: f64, range: f64, width: u32, height: u32, algorithm: Algorithm, color_mode: ColorMode) -> Vec::<u8> {
     let mut buffer = Vec::<u8>::new();
     let header = format!("P6\n{} {}\n255\n", width, height);
     buffer.extend_from_slice(header.as_bytes());
     let step_size = range / f64::from(width);
     let start_x = focus_x - (range / 2f64);
-    let mut x = start_x;
     let mut y = focus_y + (step_size * f64::from(height) / 2f64);
+    let paired_width = width - (width % 2);
     for _h in 0..height {
-        for _w in 0..width {
-            let color: (u8, u8, u8) = match algorithm {
-                Algorithm::Mandelbrot => mandelbrot::color_point(x, y),
-            };
-
+        let mut w = 0;
+        // Each pixel's x is computed directly from its column index rather
+        // than accumulated by repeated addition, so the paired and scalar
+        // paths agree exactly on x instead of drifting apart by a ULP or two
+        // -- which, right at the escape/periodicity threshold, is enough to
+        // flip a pixel from interior to escaped.
+        while w < paired_width {
+            let x0 = start_x + (w as f64) * step_size;
+            let x1 = start_x + ((w + 1) as f64) * step_size;
+            let colors = mandelbrot::color_point_pair([x0, x1], y, algorithm, color_mode, step_size);
+            for color in colors {
+                buffer.push(color.0);
+                buffer.push(color.1);
+                buffer.push(color.2);
+            }
+            w += 2;
+        }
+        // Odd trailing column (when width is odd) falls back to the scalar path.
+        while w < width {
+            let x = start_x + (w as f64) * step_size;
+            let color = mandelbrot::color_point(x, y, algorithm, color_mode, step_size);
             buffer.push(color.0);
             buffer.push(color.1);
             buffer.push(color.2);
-
-            x += step_size;
+            w += 1;
         }
-        x = start_x;
         y -= step_size;
     }
     buffer.push(b"\n"[0]);
@@ -1,26 +1,81 @@
 use std::cmp;
 use std::convert::TryInto;
 
+use crate::ColorMode;
+use crate::Algorithm;
+
 const MAX_ATTEMPTS: u32 = 4000;
+// Bailout radius of 2^8 (squared) rather than the classic 2 gives the
+// smooth/normalized iteration count below enough headroom to settle before
+// escape, which is what keeps adjacent pixels from banding.
+const BAILOUT_RADIUS_SQUARED: f64 = 65536.0;
+// Periodicity checking: if the orbit revisits a reference point within this
+// radius, it is periodic and therefore interior, so we can bail out of the
+// MAX_ATTEMPTS loop early instead of iterating every remaining attempt.
+const PERIODICITY_EPSILON_SQUARED: f64 = 1e-24;
+const PERIODICITY_CHECK_INTERVAL: u32 = 20;
+
+// Result of iterating a single point to escape (or not).  `dist` is the
+// estimated distance in the complex plane from the pixel to the set
+// boundary, used by the distance-estimation color mode.
+#[derive(Clone, Copy)]
+struct EscapeResult {
+    mu: f64,
+    dist: f64,
+}
 
-pub fn color_point(x: f64, y: f64) -> (u8, u8, u8) {
-    let time = escape_time(x, y);
-    match time {
-        Some(t) => colorize(t),
+pub fn color_point(x: f64, y: f64, algorithm: Algorithm, color_mode: ColorMode, step_size: f64) -> (u8, u8, u8) {
+    let result = escape_time(x, y, algorithm);
+    match result {
+        Some(r) => colorize(r, color_mode, step_size),
         None => (0, 0, 0),
     }
 }
 
-fn escape_time(x: f64, y: f64) -> Option<u32> {
-    let mut z_real: f64 = 0f64;
-    let mut z_imag: f64 = 0f64;
+fn escape_time(x: f64, y: f64, algorithm: Algorithm) -> Option<EscapeResult> {
+    // Mandelbrot iterates z from the origin with the pixel as the constant c;
+    // Julia iterates z from the pixel with a fixed constant c, which is the
+    // only difference between the two fractals.  The derivative dz (tracked
+    // for distance estimation) starts at 0 for Mandelbrot since c is the
+    // pixel being differentiated against, but at 1 for Julia since z0 is
+    // the pixel and c is a fixed constant with zero derivative.
+    let (mut z_real, mut z_imag, c_real, c_imag, mut dz_real, mut dz_imag, dz_feedback) = match algorithm {
+        Algorithm::Mandelbrot => (0f64, 0f64, x, y, 0f64, 0f64, 1f64),
+        Algorithm::Julia { cx, cy } => (x, y, cx, cy, 1f64, 0f64, 0f64),
+    };
+    let mut ref_real = z_real;
+    let mut ref_imag = z_imag;
+    let mut check_interval = PERIODICITY_CHECK_INTERVAL;
+    let mut next_check = check_interval;
     for n in 0..MAX_ATTEMPTS {
-        let z = recursion(z_real, z_imag, x, y);
+        let new_dz_real = 2f64 * (z_real * dz_real - z_imag * dz_imag) + dz_feedback;
+        let new_dz_imag = 2f64 * (z_real * dz_imag + z_imag * dz_real);
+        dz_real = new_dz_real;
+        dz_imag = new_dz_imag;
+
+        let z = recursion(z_real, z_imag, c_real, c_imag);
         z_real = z.0;
         z_imag = z.1;
-        if z_real.powi(2) + z_imag.powi(2) >= 4f64 {
-            //print!("{},", n);
-            return Some(n);
+        let magnitude_squared = z_real.powi(2) + z_imag.powi(2);
+        if magnitude_squared >= BAILOUT_RADIUS_SQUARED {
+            let magnitude = magnitude_squared.sqrt();
+            let mu = (n as f64) + 1.0 - (magnitude.ln()).ln() / 2f64.ln();
+            let dz_magnitude = (dz_real.powi(2) + dz_imag.powi(2)).sqrt();
+            let dist = magnitude * magnitude.ln() / dz_magnitude;
+            return Some(EscapeResult { mu, dist });
+        }
+
+        let diff_real = z_real - ref_real;
+        let diff_imag = z_imag - ref_imag;
+        if diff_real.powi(2) + diff_imag.powi(2) < PERIODICITY_EPSILON_SQUARED {
+            return None;
+        }
+
+        if n == next_check {
+            ref_real = z_real;
+            ref_imag = z_imag;
+            check_interval *= 2;
+            next_check += check_interval;
         }
     }
     None
@@ -32,7 +87,146 @@ fn recursion(z_real: f64, z_imag: f64, x: f64, y: f64) -> (f64, f64) {
     (new_real, new_imag)
 }
 
-fn colorize(e_time: u32) -> (u8, u8, u8) {
+// Renders two horizontally adjacent pixels (same y, different x) together
+// using the `wide` crate's 2-lane f64 SIMD (stable, unlike std::simd). This
+// mirrors `escape_time` exactly, lane for lane, so results are identical to
+// calling the scalar path on each pixel separately -- it just keeps both
+// lanes marching in lockstep, freezing a lane's z/dz once it escapes or is
+// found periodic so it doesn't drift while waiting on the other lane to
+// finish. `wide`'s comparison ops return a per-lane all-bits-set/all-zero
+// mask, which `blend` uses to pick lanes and which we read as a bool by
+// comparing against 0.0 (an all-ones bit pattern reads as NaN, and NaN is
+// never equal to 0.0).
+pub fn color_point_pair(xs: [f64; 2], y: f64, algorithm: Algorithm, color_mode: ColorMode, step_size: f64) -> [(u8, u8, u8); 2] {
+    let results = escape_time_pair(xs, y, algorithm);
+    [
+        match results[0] {
+            Some(r) => colorize(r, color_mode, step_size),
+            None => (0, 0, 0),
+        },
+        match results[1] {
+            Some(r) => colorize(r, color_mode, step_size),
+            None => (0, 0, 0),
+        },
+    ]
+}
+
+// Encodes a per-lane "finished" flag as 1.0/0.0 so it can be compared against
+// zero to produce a wide mask -- wide's comparison ops expect f64 lanes, not
+// bools, so the bookkeeping bools have to be lifted into f64x2 this way.
+fn finished_vec(finished: [bool; 2]) -> wide::f64x2 {
+    wide::f64x2::new([
+        if finished[0] { 1.0 } else { 0.0 },
+        if finished[1] { 1.0 } else { 0.0 },
+    ])
+}
+
+fn escape_time_pair(xs: [f64; 2], y: f64, algorithm: Algorithm) -> [Option<EscapeResult>; 2] {
+    use wide::f64x2;
+    use wide::{CmpEq, CmpGe, CmpLt};
+
+    let x_vec = f64x2::new(xs);
+    let y_vec = f64x2::splat(y);
+    let zero = f64x2::splat(0.0);
+    let one = f64x2::splat(1.0);
+    let two = f64x2::splat(2.0);
+
+    // Same Mandelbrot-vs-Julia setup as the scalar escape_time, just lane-packed.
+    let (mut z_real, mut z_imag, c_real, c_imag, mut dz_real, mut dz_imag, dz_feedback) = match algorithm {
+        Algorithm::Mandelbrot => (zero, zero, x_vec, y_vec, zero, zero, one),
+        Algorithm::Julia { cx, cy } => (x_vec, y_vec, f64x2::splat(cx), f64x2::splat(cy), one, zero, zero),
+    };
+
+    let mut ref_real = z_real;
+    let mut ref_imag = z_imag;
+    let mut check_interval = PERIODICITY_CHECK_INTERVAL;
+    let mut next_check = check_interval;
+
+    let bailout = f64x2::splat(BAILOUT_RADIUS_SQUARED);
+    let epsilon = f64x2::splat(PERIODICITY_EPSILON_SQUARED);
+
+    let mut escaped = [false; 2];
+    let mut finished = [false; 2];
+    let mut mu_out = [0f64; 2];
+    let mut dist_out = [0f64; 2];
+
+    for n in 0..MAX_ATTEMPTS {
+        let active_mask = finished_vec(finished).cmp_eq(zero);
+
+        let new_dz_real = (z_real * dz_real - z_imag * dz_imag) * two + dz_feedback;
+        let new_dz_imag = (z_real * dz_imag + z_imag * dz_real) * two;
+        dz_real = active_mask.blend(new_dz_real, dz_real);
+        dz_imag = active_mask.blend(new_dz_imag, dz_imag);
+
+        let new_z_real = z_real * z_real - z_imag * z_imag + c_real;
+        let new_z_imag = z_real * z_imag * two + c_imag;
+        z_real = active_mask.blend(new_z_real, z_real);
+        z_imag = active_mask.blend(new_z_imag, z_imag);
+
+        let magnitude_squared = z_real * z_real + z_imag * z_imag;
+        let escaping_now = active_mask & magnitude_squared.cmp_ge(bailout);
+        let escaping_arr = escaping_now.to_array();
+        if escaping_arr[0] != 0.0 || escaping_arr[1] != 0.0 {
+            // mu and dist are finished per lane with scalar f64 math, exactly
+            // as escape_time computes them, since wide's vector ln is its own
+            // approximation and would not round identically.
+            let magnitude_squared_arr = magnitude_squared.to_array();
+            let dz_real_arr = dz_real.to_array();
+            let dz_imag_arr = dz_imag.to_array();
+            for lane in 0..2 {
+                if escaping_arr[lane] != 0.0 {
+                    let magnitude = magnitude_squared_arr[lane].sqrt();
+                    let dz_magnitude = (dz_real_arr[lane].powi(2) + dz_imag_arr[lane].powi(2)).sqrt();
+                    escaped[lane] = true;
+                    finished[lane] = true;
+                    mu_out[lane] = (n as f64) + 1.0 - (magnitude.ln()).ln() / 2f64.ln();
+                    dist_out[lane] = magnitude * magnitude.ln() / dz_magnitude;
+                }
+            }
+        }
+        if finished[0] && finished[1] {
+            break;
+        }
+
+        let diff_real = z_real - ref_real;
+        let diff_imag = z_imag - ref_imag;
+        let periodic_now = active_mask & (diff_real * diff_real + diff_imag * diff_imag).cmp_lt(epsilon);
+        let periodic_arr = periodic_now.to_array();
+        if periodic_arr[0] != 0.0 || periodic_arr[1] != 0.0 {
+            for lane in 0..2 {
+                if periodic_arr[lane] != 0.0 {
+                    finished[lane] = true;
+                }
+            }
+        }
+        if finished[0] && finished[1] {
+            break;
+        }
+
+        if n == next_check {
+            let still_active = finished_vec(finished).cmp_eq(zero);
+            ref_real = still_active.blend(z_real, ref_real);
+            ref_imag = still_active.blend(z_imag, ref_imag);
+            check_interval *= 2;
+            next_check += check_interval;
+        }
+    }
+
+    [
+        if escaped[0] { Some(EscapeResult { mu: mu_out[0], dist: dist_out[0] }) } else { None },
+        if escaped[1] { Some(EscapeResult { mu: mu_out[1], dist: dist_out[1] }) } else { None },
+    ]
+}
+
+fn colorize(result: EscapeResult, color_mode: ColorMode, step_size: f64) -> (u8, u8, u8) {
+    match color_mode {
+        ColorMode::Classic => colorize_classic(result.mu as u32),
+        ColorMode::Smooth => colorize_smooth(result.mu),
+        ColorMode::Distance => colorize_distance(result.dist / step_size),
+    }
+}
+
+fn colorize_classic(e_time: u32) -> (u8, u8, u8) {
     let half_time = e_time / 2;
     let red = cmp::min(half_time, 255);
     let green = if half_time < 256 {
@@ -47,3 +241,25 @@ fn colorize(e_time: u32) -> (u8, u8, u8) {
     };
     (red.try_into().unwrap(), green.try_into().unwrap(), blue.try_into().unwrap())
 }
+
+// Continuous cosine-gradient palette driven by the fractional (normalized)
+// iteration count, so neighboring pixels blend instead of banding.
+fn colorize_smooth(mu: f64) -> (u8, u8, u8) {
+    let t = mu * 0.05;
+    let red = 0.5 + 0.5 * (std::f64::consts::TAU * (t + 0.0)).cos();
+    let green = 0.5 + 0.5 * (std::f64::consts::TAU * (t + 0.33)).cos();
+    let blue = 0.5 + 0.5 * (std::f64::consts::TAU * (t + 0.67)).cos();
+    (
+        (red * 255.0) as u8,
+        (green * 255.0) as u8,
+        (blue * 255.0) as u8,
+    )
+}
+
+// Maps a boundary distance already scaled to pixel units to brightness: a
+// point within about a pixel of the boundary is rendered white and fades to
+// black further out, giving crisp filament detail even at extreme zoom.
+fn colorize_distance(pixel_dist: f64) -> (u8, u8, u8) {
+    let brightness = ((1.0 - pixel_dist.min(1.0)) * 255.0) as u8;
+    (brightness, brightness, brightness)
+}